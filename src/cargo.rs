@@ -1,4 +1,5 @@
 use crate::ConanBuildInfo;
+use regex::Regex;
 use std::collections::HashMap;
 use std::env;
 
@@ -17,6 +18,26 @@ lazy_static! {
         "arm" => "armv7",
         "aarch64" => "armv8"
     );
+    // Architectures whose cargo name is ambiguous without endianness: the
+    // conan name differs depending on CARGO_CFG_TARGET_ENDIAN.
+    static ref CARGO_ARCH_TO_CONAN_ARCH_BY_ENDIAN: HashMap<&'static str, (&'static str, &'static str)> = hashmap!(
+        // (big endian, little endian)
+        "mips" => ("mips", "mipsel"),
+        "mips64" => ("mips64", "mips64el"),
+        "powerpc64" => ("ppc64", "ppc64le")
+    );
+    static ref CARGO_ENV_TO_CONAN_COMPILER: HashMap<(&'static str, &'static str), &'static str> = hashmap!(
+        ("windows", "msvc") => "Visual Studio",
+        ("windows", "gnu") => "gcc",
+        ("macos", "") => "apple-clang",
+        ("ios", "") => "apple-clang",
+        ("android", "") => "clang"
+    );
+    static ref COMPILER_VERSION_REGEX: HashMap<&'static str, Regex> = hashmap!(
+        "gcc" => Regex::new(r"gcc.* ([\d]+)\.[\d]+\.[\d]+").unwrap(),
+        "clang" => Regex::new(r"clang version ([\d]+)\.[\d]+\.[\d]+").unwrap(),
+        "apple-clang" => Regex::new(r"clang version ([\d]+)\.[\d]+\.[\d]+").unwrap()
+    );
 }
 
 #[cfg(feature = "cargo")]
@@ -24,7 +45,6 @@ pub fn cargo_os_to_conan_os(os_name: &str) -> &str {
     CARGO_OS_TO_CONAN_OS.get(os_name).unwrap_or(&os_name)
 }
 
-// TODO: Some arch contains endian information
 #[cfg(feature = "cargo")]
 pub fn cargo_arch_to_conan_arch(arch_name: &str) -> &str {
     CARGO_ARCH_TO_CONAN_ARCH
@@ -32,6 +52,38 @@ pub fn cargo_arch_to_conan_arch(arch_name: &str) -> &str {
         .unwrap_or(&arch_name)
 }
 
+/// Like [`cargo_arch_to_conan_arch`], but also resolves the arch/endian
+/// pairs (`mips`/`mipsel`, `powerpc64`/`powerpc64le`) that cargo encodes as
+/// `CARGO_CFG_TARGET_ARCH` + `CARGO_CFG_TARGET_ENDIAN` rather than as a
+/// single name.
+#[cfg(feature = "cargo")]
+pub fn cargo_arch_to_conan_arch_with_endian<'a>(arch_name: &'a str, endian: &str) -> &'a str {
+    if let Some((big, little)) = CARGO_ARCH_TO_CONAN_ARCH_BY_ENDIAN.get(arch_name) {
+        return if endian == "little" { little } else { big };
+    }
+
+    cargo_arch_to_conan_arch(arch_name)
+}
+
+/// Best-effort detection of the active compiler's major version by invoking
+/// it with `--version` and matching the output, mirroring how
+/// [`crate::Conan::determine_version`] extracts the conan version.
+///
+/// Only meaningful when we're not cross-compiling: this shells out to the
+/// `compiler_command` found on the *host* `PATH`, which is the target
+/// compiler only when `TARGET == HOST`. Callers must check that first.
+#[cfg(feature = "cargo")]
+fn detect_compiler_version(compiler_command: &str, conan_compiler: &str) -> Option<String> {
+    let regex = COMPILER_VERSION_REGEX.get(conan_compiler)?;
+    let output = std::process::Command::new(compiler_command)
+        .arg("--version")
+        .output()
+        .ok()?;
+    let output_string = String::from_utf8(output.stdout).ok()?;
+    let found_version = regex.captures(&output_string)?.get(1)?;
+    Some(found_version.as_str().into())
+}
+
 pub fn cargo_profile_to_conan_build_type(profile: &str) -> &str {
     match profile {
         "debug" => "Debug",
@@ -44,12 +96,17 @@ pub fn cargo_profile_to_conan_build_type(profile: &str) -> &str {
 pub fn auto_detect_settings_from_cargo() -> HashMap<String, String> {
     let mut result = HashMap::new();
 
-    if let Ok(os) = env::var("CARGO_CFG_TARGET_OS") {
-        result.insert("os".into(), cargo_os_to_conan_os(&os).into());
+    let os = env::var("CARGO_CFG_TARGET_OS").ok();
+    if let Some(os) = &os {
+        result.insert("os".into(), cargo_os_to_conan_os(os).into());
     }
 
     if let Ok(arch) = env::var("CARGO_CFG_TARGET_ARCH") {
-        result.insert("arch".into(), cargo_arch_to_conan_arch(&arch).into());
+        let endian = env::var("CARGO_CFG_TARGET_ENDIAN").unwrap_or_default();
+        result.insert(
+            "arch".into(),
+            cargo_arch_to_conan_arch_with_endian(&arch, &endian).into(),
+        );
     }
 
     if let Ok(profile) = env::var("PROFILE") {
@@ -59,9 +116,74 @@ pub fn auto_detect_settings_from_cargo() -> HashMap<String, String> {
         );
     }
 
+    // `IPHONEOS_DEPLOYMENT_TARGET`/`ANDROID_NATIVE_API_LEVEL` describe the
+    // deployment target, not anything derivable from the Cargo target
+    // triple; leaving `os.version` unset lets callers override it
+    // explicitly instead of us silently no-oping for the common case where
+    // these are unset.
+    match os.as_deref() {
+        Some("ios") => {
+            if let Ok(deployment_target) = env::var("IPHONEOS_DEPLOYMENT_TARGET") {
+                result.insert("os.version".into(), deployment_target);
+            }
+        }
+        Some("android") => {
+            if let Ok(api_level) = env::var("ANDROID_NATIVE_API_LEVEL") {
+                result.insert("os.version".into(), api_level);
+            }
+        }
+        _ => {}
+    }
+
+    // Conan has no standard Linux setting for the libc flavor (`musl` vs
+    // `gnu`): `os.subsystem` only exists under `os=Windows`
+    // (`cygwin`/`msys`/`msys2`/`wsl`), so inventing `os.subsystem=gnu` here
+    // made every default `*-unknown-linux-gnu` build emit an invalid
+    // setting and fail `conan install`. There's nothing to insert for it.
+    if let Ok(target_env) = env::var("CARGO_CFG_TARGET_ENV") {
+        let conan_compiler = CARGO_ENV_TO_CONAN_COMPILER
+            .get(&(os.as_deref().unwrap_or(""), target_env.as_str()))
+            .or_else(|| CARGO_ENV_TO_CONAN_COMPILER.get(&(os.as_deref().unwrap_or(""), "")));
+        if let Some(conan_compiler) = conan_compiler {
+            result.insert("compiler".into(), (*conan_compiler).into());
+
+            // Detecting the version means invoking `compiler_command` off
+            // the host `PATH`, which only reflects the target toolchain
+            // when we aren't cross-compiling.
+            let is_cross_compiling = env::var("TARGET")
+                .ok()
+                .zip(env::var("HOST").ok())
+                .is_some_and(|(target, host)| target != host);
+
+            if !is_cross_compiling {
+                let compiler_command = match *conan_compiler {
+                    "gcc" => Some("gcc"),
+                    "clang" | "apple-clang" => Some("clang"),
+                    _ => None,
+                };
+                if let Some(compiler_command) = compiler_command {
+                    if let Some(version) = detect_compiler_version(compiler_command, conan_compiler)
+                    {
+                        result.insert("compiler.version".into(), version);
+                    }
+                }
+            }
+        }
+    }
+
     result
 }
 
+/// Emits `cargo:` build-script directives for `build_info`.
+///
+/// The `cargo:include=`/`cargo:CONAN_<DEP>_*` metadata keys are
+/// "downstream" metadata: Cargo only passes them to dependent build
+/// scripts (as `DEP_<LINKS>_<KEY>` env vars) when this package declares a
+/// `links` key, which a generic build-info consumer like this one can't
+/// do on a dependent's behalf. They're still useful as plain output for
+/// inspection or for a build script that reads its own stdout-adjacent
+/// state, but won't be visible to *other* crates' build scripts unless
+/// the caller's own `Cargo.toml` sets `links`.
 #[cfg(feature = "cargo")]
 pub fn output_information_to_cargo(build_info: &ConanBuildInfo) {
     for dependency in &build_info.dependencies {
@@ -74,5 +196,73 @@ pub fn output_information_to_cargo(build_info: &ConanBuildInfo) {
         for system_lib in &dependency.system_libs {
             println!("cargo:rustc-link-lib={}", system_lib);
         }
+        for include_path in &dependency.include_paths {
+            println!("cargo:include={}", include_path);
+        }
+        for framework_path in &dependency.framework_paths {
+            println!("cargo:rustc-link-search=framework={}", framework_path);
+        }
+        for framework in &dependency.frameworks {
+            // `rustc-link-arg` only applies to the current crate's own
+            // binary/cdylib/test link, not a `-sys` crate's dependents;
+            // `rustc-link-lib=framework=` is the form that actually
+            // propagates, mirroring the plain `rustc-link-lib` above.
+            println!("cargo:rustc-link-lib=framework={}", framework);
+        }
+
+        let dep_key = dependency.name.to_uppercase().replace('-', "_");
+        if !dependency.defines.is_empty() {
+            println!(
+                "cargo:CONAN_{}_DEFINES={}",
+                dep_key,
+                dependency.defines.join(";")
+            );
+        }
+        if !dependency.cflags.is_empty() {
+            println!(
+                "cargo:CONAN_{}_CFLAGS={}",
+                dep_key,
+                dependency.cflags.join(";")
+            );
+        }
+        if !dependency.cxxflags.is_empty() {
+            println!(
+                "cargo:CONAN_{}_CXXFLAGS={}",
+                dep_key,
+                dependency.cxxflags.join(";")
+            );
+        }
+    }
+}
+
+/// Applies `build_info`'s include dirs, defines, and compiler flags to a
+/// `cc::Build`. `cflags` apply regardless of language, but `cxxflags` are
+/// C++-only and are skipped unless `is_cpp` is `true` — handing a C++-only
+/// flag like `-std=c++17` to a C compile errors or warns, so the caller
+/// must say which language `build` is compiling.
+#[cfg(feature = "cc")]
+pub fn apply_to_cc(build_info: &ConanBuildInfo, build: &mut cc::Build, is_cpp: bool) {
+    for dependency in &build_info.dependencies {
+        for include_path in &dependency.include_paths {
+            build.include(include_path);
+        }
+        for define in &dependency.defines {
+            match define.split_once('=') {
+                Some((key, value)) => {
+                    build.define(key, value);
+                }
+                None => {
+                    build.define(define, None);
+                }
+            }
+        }
+        for cflag in &dependency.cflags {
+            build.flag(cflag);
+        }
+        if is_cpp {
+            for cxxflag in &dependency.cxxflags {
+                build.flag(cxxflag);
+            }
+        }
     }
 }
@@ -5,6 +5,7 @@ extern crate maplit;
 
 use regex::Regex;
 use serde::Deserialize;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::process::Command;
 use std::string::String;
@@ -12,22 +13,64 @@ use std::string::String;
 #[cfg(feature = "cargo")]
 pub mod cargo;
 
+/// An argv that a dry-run [`Conan`] recorded instead of executing.
+#[derive(Debug, Clone)]
+pub struct RecordedCommand {
+    pub program: std::path::PathBuf,
+    pub args: Vec<String>,
+}
+
 pub struct Conan {
     pub path: std::path::PathBuf,
+    dry_run: Cell<bool>,
+    recorded_commands: RefCell<Vec<RecordedCommand>>,
 }
 
 impl Conan {
     pub fn new(conan_path: std::path::PathBuf) -> Conan {
-        Conan { path: conan_path }
+        Conan {
+            path: conan_path,
+            dry_run: Cell::new(false),
+            recorded_commands: RefCell::new(Vec::new()),
+        }
     }
 
     pub fn find_system_conan() -> Option<Conan> {
         if let Ok(conan_path) = which::which("conan") {
-            return Some(Conan { path: conan_path });
+            return Some(Conan::new(conan_path));
         }
 
         None
     }
+
+    /// Enables or disables dry-run mode. While enabled, `add_remote`,
+    /// `remove_remote` and `create_install_command`/`create_install_command_for`
+    /// log the argv they would have run via [`Conan::recorded_commands`]
+    /// instead of spawning the conan process.
+    pub fn set_dry_run(&self, value: bool) {
+        self.dry_run.set(value);
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.get()
+    }
+
+    pub fn recorded_commands(&self) -> Vec<RecordedCommand> {
+        self.recorded_commands.borrow().clone()
+    }
+
+    /// Returns and clears the commands recorded so far.
+    pub fn take_recorded_commands(&self) -> Vec<RecordedCommand> {
+        self.recorded_commands.replace(Vec::new())
+    }
+
+    fn record_command(&self, args: Vec<String>) {
+        println!("conan (dry-run): {:?} {:?}", &self.path, &args);
+        self.recorded_commands.borrow_mut().push(RecordedCommand {
+            program: self.path.clone(),
+            args,
+        });
+    }
 }
 
 lazy_static! {
@@ -52,6 +95,12 @@ impl Remote {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConanVersion {
+    V1,
+    V2,
+}
+
 impl Conan {
     pub fn determine_version(&self) -> Option<String> {
         let output = Command::new(&self.path)
@@ -66,6 +115,14 @@ impl Conan {
         Some(found_version.as_str().into())
     }
 
+    pub fn determine_conan_version(&self) -> Option<ConanVersion> {
+        let version = self.determine_version()?;
+        match version.split('.').next()? {
+            "1" => Some(ConanVersion::V1),
+            _ => Some(ConanVersion::V2),
+        }
+    }
+
     pub fn get_remote_list(&self) -> Option<Vec<Remote>> {
         let output = Command::new(&self.path)
             .arg("remote")
@@ -93,7 +150,6 @@ impl Conan {
     }
 
     pub fn add_remote(&self, remote: &Remote, index: Option<u32>, force: bool) -> bool {
-        let mut command = Command::new(&self.path);
         let mut arguments = vec!["remote".to_owned(), "add".to_owned()];
         if let Some(index) = index {
             arguments.push("-i".into());
@@ -115,22 +171,230 @@ impl Conan {
             None => {}
         }
 
-        command.spawn().unwrap().wait().is_ok()
+        if self.is_dry_run() {
+            self.record_command(arguments);
+            return true;
+        }
+
+        Command::new(&self.path)
+            .args(arguments)
+            .spawn()
+            .unwrap()
+            .wait()
+            .is_ok()
     }
 
     pub fn remove_remote(&self, remote_name: &str) -> bool {
+        let arguments = vec![
+            "remote".to_owned(),
+            "remove".to_owned(),
+            remote_name.to_owned(),
+        ];
+
+        if self.is_dry_run() {
+            self.record_command(arguments);
+            return true;
+        }
+
         Command::new(&self.path)
-            .arg("remote")
-            .arg("remove")
-            .arg(remote_name)
+            .args(arguments)
             .spawn()
             .unwrap()
             .wait()
             .is_ok()
     }
+
+    /// Authenticates against `remote` (`conan user -p <password> -r <remote> <user>`)
+    /// so a following [`Conan::create_upload_command`] doesn't get rejected.
+    pub fn user_login(&self, remote: &str, user: &str, password: &str) -> bool {
+        let arguments = vec![
+            "user".to_owned(),
+            "-p".to_owned(),
+            password.to_owned(),
+            "-r".to_owned(),
+            remote.to_owned(),
+            user.to_owned(),
+        ];
+
+        if self.is_dry_run() {
+            self.record_command(arguments);
+            return true;
+        }
+
+        Command::new(&self.path)
+            .args(arguments)
+            .spawn()
+            .unwrap()
+            .wait()
+            .is_ok()
+    }
+
+    /// Checks whether `remote` already has stored, non-anonymous credentials,
+    /// so a batch publish can verify auth before uploading every package.
+    pub fn verify_remote_auth(&self, remote: &str) -> bool {
+        let output = Command::new(&self.path)
+            .arg("user")
+            .arg("-r")
+            .arg(remote)
+            .output()
+            .expect(&format!(
+                "Cannot execute conan from path \"{:?}\"",
+                &self.path
+            ));
+        let output_string = String::from_utf8(output.stdout).unwrap_or_default();
+        !output_string.contains("None")
+    }
+
+    /// Wraps `conan search <pattern> [-r <remote>]`, returning the matched
+    /// package references one per line.
+    pub fn search(&self, pattern: &str, remote: Option<&str>) -> Option<Vec<String>> {
+        let mut command = Command::new(&self.path);
+        command.arg("search").arg(pattern);
+        if let Some(remote) = remote {
+            command.arg("-r").arg(remote);
+        }
+
+        let output = command.output().ok()?;
+        let output_string = String::from_utf8(output.stdout).ok()?;
+        Some(
+            output_string
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect(),
+        )
+    }
 }
 
 #[derive(Debug)]
+pub struct UploadArguments {
+    pub reference: String,
+    pub remote: String,
+    pub all: bool,
+    pub confirm: bool,
+    pub parallel: bool,
+}
+
+impl UploadArguments {
+    pub fn to_commandline_arguments(&self) -> Vec<String> {
+        let mut result = vec!["upload".into(), self.reference.clone()];
+
+        result.push("-r".into());
+        result.push(self.remote.clone());
+
+        if self.all {
+            result.push("--all".into());
+        }
+
+        if self.confirm {
+            result.push("--confirm".into());
+        }
+
+        if self.parallel {
+            result.push("--parallel".into());
+        }
+
+        result
+    }
+}
+
+pub struct UploadArgumentsBuilder {
+    reference: String,
+    remote: String,
+    all: bool,
+    confirm: bool,
+    parallel: bool,
+}
+
+impl UploadArgumentsBuilder {
+    pub fn new(reference: String, remote: String) -> UploadArgumentsBuilder {
+        UploadArgumentsBuilder {
+            reference,
+            remote,
+            all: false,
+            confirm: false,
+            parallel: false,
+        }
+    }
+
+    pub fn all(&mut self, value: bool) -> &mut UploadArgumentsBuilder {
+        self.all = value;
+        self
+    }
+
+    pub fn confirm(&mut self, value: bool) -> &mut UploadArgumentsBuilder {
+        self.confirm = value;
+        self
+    }
+
+    pub fn parallel(&mut self, value: bool) -> &mut UploadArgumentsBuilder {
+        self.parallel = value;
+        self
+    }
+
+    pub fn build(self) -> UploadArguments {
+        UploadArguments {
+            reference: self.reference,
+            remote: self.remote,
+            all: self.all,
+            confirm: self.confirm,
+            parallel: self.parallel,
+        }
+    }
+}
+
+impl Conan {
+    pub fn create_upload_command(&self, upload_arguments: &UploadArguments) -> Command {
+        let args = upload_arguments.to_commandline_arguments();
+        if self.is_dry_run() {
+            self.record_command(args.clone());
+        }
+
+        let mut command = Command::new(&self.path);
+        command.args(args);
+        command
+    }
+}
+
+#[test]
+fn test_upload_arguments() {
+    let mut builder = UploadArgumentsBuilder::new("zlib/1.2.11@_/_".into(), "conancenter".into());
+    builder.all(true).confirm(true).parallel(true);
+    let arguments = builder.build();
+    assert_eq!(
+        arguments.to_commandline_arguments(),
+        vec![
+            "upload",
+            "zlib/1.2.11@_/_",
+            "-r",
+            "conancenter",
+            "--all",
+            "--confirm",
+            "--parallel"
+        ]
+    );
+}
+
+#[test]
+fn test_dry_run_records_user_login_and_upload() {
+    let conan = Conan::new("conan".into());
+    conan.set_dry_run(true);
+
+    assert!(conan.user_login("conancenter", "someuser", "somepassword"));
+
+    let mut upload_builder =
+        UploadArgumentsBuilder::new("zlib/1.2.11@_/_".into(), "conancenter".into());
+    upload_builder.all(true);
+    let upload_arguments = upload_builder.build();
+    conan.create_upload_command(&upload_arguments);
+
+    let recorded = conan.take_recorded_commands();
+    assert_eq!(recorded.len(), 2);
+    assert_eq!(recorded[1].args, upload_arguments.to_commandline_arguments());
+}
+
+#[derive(Debug, Clone)]
 pub enum InstallTarget {
     ConanFile {
         path: String,
@@ -141,7 +405,7 @@ pub enum InstallTarget {
     },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Generator(&'static str);
 
 impl Generator {
@@ -173,12 +437,25 @@ impl Generator {
     pub const MAKE: Generator = Generator("make");
     pub const DEPLOY: Generator = Generator("deploy");
 
+    // Conan 2.x generators.
+    pub const CMAKE_DEPS: Generator = Generator("CMakeDeps");
+    pub const CMAKE_TOOLCHAIN: Generator = Generator("CMakeToolchain");
+    pub const MSBUILD_DEPS: Generator = Generator("MSBuildDeps");
+    pub const MSBUILD_TOOLCHAIN: Generator = Generator("MSBuildToolchain");
+    pub const PKG_CONFIG_DEPS: Generator = Generator("PkgConfigDeps");
+    pub const BAZEL_DEPS: Generator = Generator("BazelDeps");
+    pub const AUTOTOOLS_DEPS: Generator = Generator("AutotoolsDeps");
+
     pub fn custom(name: &'static str) -> Generator {
         Generator(name)
     }
+
+    pub fn name(&self) -> &str {
+        self.0
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum BuildConfiguration {
     All,
     Never,
@@ -188,7 +465,7 @@ pub enum BuildConfiguration {
     Package { pattern: String },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstallArguments {
     pub install_target: InstallTarget,
     pub generators: Vec<Generator>,
@@ -209,6 +486,10 @@ pub struct InstallArguments {
 
 impl InstallArguments {
     pub fn to_commandline_arguments(&self) -> Vec<String> {
+        self.to_commandline_arguments_for(ConanVersion::V1)
+    }
+
+    pub fn to_commandline_arguments_for(&self, conan_version: ConanVersion) -> Vec<String> {
         let mut result = vec!["install".into()];
 
         match &self.install_target {
@@ -228,10 +509,19 @@ impl InstallArguments {
             result.push(generator.0.into());
         }
 
-        result.push("-if".into());
+        match conan_version {
+            ConanVersion::V1 => {
+                result.push("-if".into());
+            }
+            ConanVersion::V2 => {
+                result.push("-of".into());
+            }
+        }
         result.push(self.install_folder.clone());
 
-        if self.no_imports {
+        // `--no-imports` was dropped in Conan 2 along with the `[imports]`
+        // mechanism it short-circuits; only emit it for the 1.x flavor.
+        if self.no_imports && conan_version == ConanVersion::V1 {
             result.push("--no-imports".into());
         }
 
@@ -257,18 +547,25 @@ impl InstallArguments {
             }
         }
 
-        for (env_key, env_value) in &self.envs {
-            result.push("-e".into());
-            result.push(format!("{}={}", env_key, env_value));
-        }
+        // `-e`/`-e:b` (declarative env vars) were removed in Conan 2 in
+        // favor of profile-based environment; only emit them for 1.x.
+        if conan_version == ConanVersion::V1 {
+            for (env_key, env_value) in &self.envs {
+                result.push("-e".into());
+                result.push(format!("{}={}", env_key, env_value));
+            }
 
-        for (env_key, env_value) in &self.envs_build {
-            result.push("-e:b".into());
-            result.push(format!("{}={}", env_key, env_value));
+            for (env_key, env_value) in &self.envs_build {
+                result.push("-e:b".into());
+                result.push(format!("{}={}", env_key, env_value));
+            }
         }
 
         for (option_key, option_value) in &self.options {
-            result.push("-o".into());
+            match conan_version {
+                ConanVersion::V1 => result.push("-o".into()),
+                ConanVersion::V2 => result.push("-o:h".into()),
+            }
             result.push(format!("{}={}", option_key, option_value));
         }
 
@@ -293,7 +590,10 @@ impl InstallArguments {
         }
 
         for (setting_key, setting_value) in &self.settings {
-            result.push("-s".into());
+            match conan_version {
+                ConanVersion::V1 => result.push("-s".into()),
+                ConanVersion::V2 => result.push("-s:h".into()),
+            }
             result.push(format!("{}={}", setting_key, setting_value));
         }
 
@@ -480,14 +780,543 @@ fn test_install_arguments() {
     println!("{:?}", arguments.to_commandline_arguments());
 }
 
+#[test]
+fn test_install_arguments_v2_omits_removed_v1_flags() {
+    let mut builder = InstallArgumentsBuilder::new(
+        InstallTarget::Package {
+            reference: "zlib/1.2.11@_/_".into(),
+        },
+        "build".into(),
+    );
+    builder
+        .no_imports(true)
+        .envs(hashmap!("SomeEnv".into() => "SomeValue".into()))
+        .envs_build(hashmap!("SomeBuildEnv".into() => "SomeBuildValue".into()))
+        .options(hashmap!("SomeOpt".into() => "SomeValue".into()));
+    let arguments = builder.build();
+
+    let v1_args = arguments.to_commandline_arguments_for(ConanVersion::V1);
+    assert!(v1_args.contains(&"--no-imports".to_string()));
+    assert!(v1_args.contains(&"-e".to_string()));
+    assert!(v1_args.contains(&"-e:b".to_string()));
+    assert!(v1_args.contains(&"-o".to_string()));
+
+    // `--no-imports`, `-e`/`-e:b` were removed in Conan 2; options move to
+    // `-o:h`.
+    let v2_args = arguments.to_commandline_arguments_for(ConanVersion::V2);
+    assert!(!v2_args.contains(&"--no-imports".to_string()));
+    assert!(!v2_args.contains(&"-e".to_string()));
+    assert!(!v2_args.contains(&"-e:b".to_string()));
+    assert!(!v2_args.contains(&"-o".to_string()));
+    assert!(v2_args.contains(&"-o:h".to_string()));
+}
+
+#[test]
+fn test_dry_run_records_remote_commands() {
+    let conan = Conan::new("conan".into());
+    conan.set_dry_run(true);
+
+    assert!(conan.add_remote(
+        &Remote::new("conancenter".into(), "https://center.conan.io".into()),
+        None,
+        false
+    ));
+    assert!(conan.remove_remote("conancenter"));
+
+    let recorded = conan.take_recorded_commands();
+    assert_eq!(recorded.len(), 2);
+    assert_eq!(recorded[0].args[..2], ["remote".to_owned(), "add".to_owned()]);
+    assert_eq!(
+        recorded[1].args,
+        vec!["remote".to_owned(), "remove".to_owned(), "conancenter".to_owned()]
+    );
+    assert!(conan.recorded_commands().is_empty());
+}
+
+#[test]
+fn test_dry_run_create_install_command_skips_version_detection() {
+    // A path that cannot be spawned: create_install_command must not shell
+    // out to it via determine_conan_version while dry_run is set, or this
+    // panics instead of just recording the argv.
+    let conan = Conan::new("/nonexistent/conan-does-not-exist".into());
+    conan.set_dry_run(true);
+
+    let arguments = InstallArgumentsBuilder::new(
+        InstallTarget::Package {
+            reference: "zlib/1.2.11@_/_".into(),
+        },
+        "build".into(),
+    )
+    .build();
+    conan.create_install_command(&arguments);
+
+    let recorded = conan.take_recorded_commands();
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].args[0], "install");
+}
+
 impl Conan {
+    /// Auto-detects the installed conan's major version via
+    /// [`Conan::determine_conan_version`] and emits the matching argument
+    /// flavor, falling back to the 1.x flavor if detection fails.
+    ///
+    /// Skips detection entirely in dry-run mode: a dry run previews the
+    /// plan without invoking conan at all, but `determine_conan_version`
+    /// spawns `conan --version` and `.expect()`-panics if conan isn't on
+    /// the path.
     pub fn create_install_command(&self, install_arguments: &InstallArguments) -> Command {
+        let conan_version = if self.is_dry_run() {
+            ConanVersion::V1
+        } else {
+            self.determine_conan_version().unwrap_or(ConanVersion::V1)
+        };
+        self.create_install_command_for(install_arguments, conan_version)
+    }
+
+    pub fn create_install_command_for(
+        &self,
+        install_arguments: &InstallArguments,
+        conan_version: ConanVersion,
+    ) -> Command {
+        let args = install_arguments.to_commandline_arguments_for(conan_version);
+        if self.is_dry_run() {
+            self.record_command(args.clone());
+        }
+
         let mut command = Command::new(&self.path);
-        command.args(install_arguments.to_commandline_arguments());
+        command.args(args);
         command
     }
 }
 
+/// A typed model of a `conanfile.txt`, the declarative counterpart to a
+/// full `conanfile.py`. Feed the path passed to [`ConanFile::write_to`]
+/// into `InstallTarget::ConanFile { path, .. }` to drive an install from
+/// requirements declared in a build script instead of a checked-in file.
+#[derive(Debug, Default)]
+pub struct ConanFile {
+    pub requires: Vec<String>,
+    pub build_requires: Vec<String>,
+    pub generators: Vec<String>,
+    pub options: HashMap<String, String>,
+    pub imports: Vec<String>,
+    /// Sections this crate doesn't model explicitly (e.g. `[system_requires]`),
+    /// preserved verbatim as their raw lines so a round-tripped file doesn't
+    /// lose information.
+    pub other_sections: HashMap<String, Vec<String>>,
+}
+
+impl ConanFile {
+    pub fn new() -> ConanFile {
+        Default::default()
+    }
+
+    pub fn generators(&mut self, value: Vec<Generator>) -> &mut ConanFile {
+        self.generators = value.iter().map(|generator| generator.name().to_owned()).collect();
+        self
+    }
+
+    pub fn write_to(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut file = std::fs::File::create(path)?;
+
+        if !self.requires.is_empty() {
+            writeln!(file, "[requires]")?;
+            for require in &self.requires {
+                writeln!(file, "{}", require)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !self.build_requires.is_empty() {
+            writeln!(file, "[build_requires]")?;
+            for build_require in &self.build_requires {
+                writeln!(file, "{}", build_require)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !self.generators.is_empty() {
+            writeln!(file, "[generators]")?;
+            for generator in &self.generators {
+                writeln!(file, "{}", generator)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !self.options.is_empty() {
+            writeln!(file, "[options]")?;
+            for (option_key, option_value) in &self.options {
+                writeln!(file, "{}={}", option_key, option_value)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !self.imports.is_empty() {
+            writeln!(file, "[imports]")?;
+            for import in &self.imports {
+                writeln!(file, "{}", import)?;
+            }
+            writeln!(file)?;
+        }
+
+        for (section, lines) in &self.other_sections {
+            writeln!(file, "[{}]", section)?;
+            for line in lines {
+                writeln!(file, "{}", line)?;
+            }
+            writeln!(file)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn parse(content: &str) -> ConanFile {
+        let mut result = ConanFile::new();
+        let mut current_section: Option<String> = None;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                current_section = Some(trimmed[1..trimmed.len() - 1].to_owned());
+                continue;
+            }
+
+            match current_section.as_deref() {
+                Some("requires") => result.requires.push(trimmed.to_owned()),
+                Some("build_requires") => result.build_requires.push(trimmed.to_owned()),
+                Some("generators") => result.generators.push(trimmed.to_owned()),
+                Some("imports") => result.imports.push(trimmed.to_owned()),
+                Some("options") => {
+                    if let Some((option_key, option_value)) = trimmed.split_once('=') {
+                        result
+                            .options
+                            .insert(option_key.trim().to_owned(), option_value.trim().to_owned());
+                    }
+                }
+                Some(section) => {
+                    result
+                        .other_sections
+                        .entry(section.to_owned())
+                        .or_default()
+                        .push(line.to_owned());
+                }
+                None => {}
+            }
+        }
+
+        result
+    }
+
+    pub fn parse_file(path: impl AsRef<std::path::Path>) -> std::io::Result<ConanFile> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(ConanFile::parse(&content))
+    }
+}
+
+#[test]
+fn test_conan_file_round_trips_through_text() {
+    let mut conan_file = ConanFile::new();
+    conan_file.requires.push("zlib/1.2.11".into());
+    conan_file.build_requires.push("cmake/3.21.0".into());
+    conan_file.generators(vec![Generator::CMAKE, Generator::JSON]);
+    conan_file
+        .options
+        .insert("zlib:shared".into(), "True".into());
+    conan_file.imports.push("bin, *.dll -> ./bin".into());
+
+    let mut text = Vec::new();
+    {
+        use std::io::Write;
+        writeln!(text, "[requires]").unwrap();
+        writeln!(text, "zlib/1.2.11").unwrap();
+        writeln!(text).unwrap();
+        writeln!(text, "[build_requires]").unwrap();
+        writeln!(text, "cmake/3.21.0").unwrap();
+        writeln!(text).unwrap();
+        writeln!(text, "# a comment").unwrap();
+        writeln!(text, "[generators]").unwrap();
+        writeln!(text, "cmake").unwrap();
+        writeln!(text, "json").unwrap();
+        writeln!(text).unwrap();
+        writeln!(text, "[options]").unwrap();
+        writeln!(text, "zlib:shared=True").unwrap();
+        writeln!(text).unwrap();
+        writeln!(text, "[imports]").unwrap();
+        writeln!(text, "bin, *.dll -> ./bin").unwrap();
+    }
+    let text = String::from_utf8(text).unwrap();
+
+    let parsed = ConanFile::parse(&text);
+    assert_eq!(parsed.requires, vec!["zlib/1.2.11".to_string()]);
+    assert_eq!(parsed.build_requires, vec!["cmake/3.21.0".to_string()]);
+    assert_eq!(parsed.generators, vec!["cmake".to_string(), "json".to_string()]);
+    assert_eq!(
+        parsed.options.get("zlib:shared").map(String::as_str),
+        Some("True")
+    );
+    assert_eq!(parsed.imports, vec!["bin, *.dll -> ./bin".to_string()]);
+
+    // Round trip `conan_file` itself through `write_to`/`parse_file`.
+    let path = std::env::temp_dir().join("conan_wrapper_test_conanfile.txt");
+    conan_file.write_to(&path).unwrap();
+    let reparsed = ConanFile::parse_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(reparsed.requires, conan_file.requires);
+    assert_eq!(reparsed.build_requires, conan_file.build_requires);
+    assert_eq!(reparsed.generators, conan_file.generators);
+    assert_eq!(reparsed.options, conan_file.options);
+    assert_eq!(reparsed.imports, conan_file.imports);
+}
+
+#[derive(Debug, Clone)]
+pub struct PackageConfiguration {
+    pub arch: Option<String>,
+    pub compiler_version: Option<String>,
+    pub build_type: Option<String>,
+    pub options: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+pub struct PackageResult {
+    pub configuration: PackageConfiguration,
+    pub success: bool,
+}
+
+pub struct ConanMultiPackagerBuilder {
+    base_arguments: InstallArguments,
+    archs: Vec<String>,
+    compiler_versions: Vec<String>,
+    build_types: Vec<String>,
+    option_permutations: Vec<HashMap<String, String>>,
+    remotes: Vec<String>,
+}
+
+impl ConanMultiPackagerBuilder {
+    pub fn new(base_arguments: InstallArguments) -> ConanMultiPackagerBuilder {
+        ConanMultiPackagerBuilder {
+            base_arguments,
+            archs: Vec::new(),
+            compiler_versions: Vec::new(),
+            build_types: Vec::new(),
+            option_permutations: Vec::new(),
+            remotes: Vec::new(),
+        }
+    }
+
+    pub fn archs(&mut self, value: Vec<String>) -> &mut ConanMultiPackagerBuilder {
+        self.archs = value;
+        self
+    }
+
+    pub fn compiler_versions(&mut self, value: Vec<String>) -> &mut ConanMultiPackagerBuilder {
+        self.compiler_versions = value;
+        self
+    }
+
+    pub fn build_types(&mut self, value: Vec<String>) -> &mut ConanMultiPackagerBuilder {
+        self.build_types = value;
+        self
+    }
+
+    pub fn option_permutations(
+        &mut self,
+        value: Vec<HashMap<String, String>>,
+    ) -> &mut ConanMultiPackagerBuilder {
+        self.option_permutations = value;
+        self
+    }
+
+    pub fn remotes(&mut self, value: Vec<String>) -> &mut ConanMultiPackagerBuilder {
+        self.remotes = value;
+        self
+    }
+
+    pub fn build(self) -> ConanMultiPackager {
+        ConanMultiPackager {
+            base_arguments: self.base_arguments,
+            archs: self.archs,
+            compiler_versions: self.compiler_versions,
+            build_types: self.build_types,
+            option_permutations: self.option_permutations,
+            remotes: self.remotes,
+        }
+    }
+}
+
+pub struct ConanMultiPackager {
+    base_arguments: InstallArguments,
+    archs: Vec<String>,
+    compiler_versions: Vec<String>,
+    build_types: Vec<String>,
+    option_permutations: Vec<HashMap<String, String>>,
+    remotes: Vec<String>,
+}
+
+impl ConanMultiPackager {
+    fn non_empty_or_placeholder(values: &[String]) -> Vec<Option<&String>> {
+        if values.is_empty() {
+            vec![None]
+        } else {
+            values.iter().map(Some).collect()
+        }
+    }
+
+    pub fn expand(&self) -> Vec<InstallArguments> {
+        let archs = Self::non_empty_or_placeholder(&self.archs);
+        let compiler_versions = Self::non_empty_or_placeholder(&self.compiler_versions);
+        let build_types = Self::non_empty_or_placeholder(&self.build_types);
+        let option_permutations: Vec<Option<&HashMap<String, String>>> =
+            if self.option_permutations.is_empty() {
+                vec![None]
+            } else {
+                self.option_permutations.iter().map(Some).collect()
+            };
+
+        let mut result = Vec::new();
+        for arch in &archs {
+            for compiler_version in &compiler_versions {
+                for build_type in &build_types {
+                    for options in &option_permutations {
+                        let mut arguments = self.base_arguments.clone();
+
+                        if let Some(arch) = arch {
+                            arguments.settings.insert("arch".into(), (*arch).clone());
+                        }
+                        if let Some(compiler_version) = compiler_version {
+                            arguments
+                                .settings
+                                .insert("compiler.version".into(), (*compiler_version).clone());
+                        }
+                        if let Some(build_type) = build_type {
+                            arguments.settings.insert("build_type".into(), (*build_type).clone());
+                        }
+                        if let Some(options) = options {
+                            for (option_key, option_value) in options.iter() {
+                                arguments
+                                    .options
+                                    .insert(option_key.clone(), option_value.clone());
+                            }
+                        }
+
+                        result.push(arguments);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    pub fn run(&self, conan: &Conan) -> Vec<PackageResult> {
+        self.expand()
+            .into_iter()
+            .map(|mut arguments| {
+                // A shared set of remotes to install from: search all of
+                // conan's configured remotes (`-r all`) instead of pinning
+                // each configuration to one of them via round-robin, which
+                // would fail whenever that one remote doesn't carry the
+                // package.
+                if !self.remotes.is_empty() {
+                    arguments.remote = Some("all".into());
+                }
+
+                let configuration = PackageConfiguration {
+                    arch: arguments.settings.get("arch").cloned(),
+                    compiler_version: arguments.settings.get("compiler.version").cloned(),
+                    build_type: arguments.settings.get("build_type").cloned(),
+                    options: arguments.options.clone(),
+                };
+
+                // `create_install_command` only records the argv in
+                // dry-run mode, it still returns a live `Command` — spawn
+                // it only when we're not previewing, so a dry run doesn't
+                // perform real installs.
+                let success = if conan.is_dry_run() {
+                    conan.create_install_command(&arguments);
+                    true
+                } else {
+                    conan
+                        .create_install_command(&arguments)
+                        .spawn()
+                        .and_then(|mut child| child.wait())
+                        .map(|status| status.success())
+                        .unwrap_or(false)
+                };
+
+                PackageResult {
+                    configuration,
+                    success,
+                }
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_multi_packager_expand() {
+    let base_arguments = InstallArgumentsBuilder::new(
+        InstallTarget::ConanFile {
+            path: "conanfile.txt".into(),
+            reference: None,
+        },
+        "build".into(),
+    )
+    .build();
+
+    let mut builder = ConanMultiPackagerBuilder::new(base_arguments);
+    builder
+        .archs(vec!["x86".into(), "x86_64".into()])
+        .build_types(vec!["Debug".into(), "Release".into()]);
+    let multi_packager = builder.build();
+
+    let expanded = multi_packager.expand();
+    assert_eq!(expanded.len(), 4);
+    for arguments in &expanded {
+        assert!(arguments.settings.contains_key("arch"));
+        assert!(arguments.settings.contains_key("build_type"));
+    }
+}
+
+#[test]
+fn test_multi_packager_run_dry_run_does_not_spawn() {
+    // A path that cannot be spawned: run() must not spawn the recorded
+    // command while dry_run is set, or this panics instead of reporting a
+    // synthetic success per configuration.
+    let conan = Conan::new("/nonexistent/conan-does-not-exist".into());
+    conan.set_dry_run(true);
+
+    let base_arguments = InstallArgumentsBuilder::new(
+        InstallTarget::Package {
+            reference: "zlib/1.2.11@_/_".into(),
+        },
+        "build".into(),
+    )
+    .build();
+
+    let mut builder = ConanMultiPackagerBuilder::new(base_arguments);
+    builder
+        .build_types(vec!["Debug".into(), "Release".into()])
+        .remotes(vec!["conancenter".into()]);
+    let multi_packager = builder.build();
+
+    let results = multi_packager.run(&conan);
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|result| result.success));
+
+    let recorded = conan.take_recorded_commands();
+    assert_eq!(recorded.len(), 2);
+    for command in &recorded {
+        assert!(command.args.contains(&"-r".to_string()));
+        assert!(command.args.contains(&"all".to_string()));
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DependencyInfo {
     pub name: String,
@@ -535,6 +1364,190 @@ impl ConanBuildInfo {
     }
 }
 
+/// The `cpp_info` block of a single component within a Conan 2.x
+/// `conan install --format=json` graph node. Conan 2 nests this per
+/// component (with `"root"` being the node's own, unnamed component)
+/// rather than exposing the flat fields `DependencyInfo` has for 1.x.
+#[derive(Debug, Default, Deserialize)]
+pub struct ConanV2CppInfo {
+    #[serde(default)]
+    pub includedirs: Vec<String>,
+    #[serde(default)]
+    pub libdirs: Vec<String>,
+    #[serde(default)]
+    pub bindirs: Vec<String>,
+    #[serde(default)]
+    pub libs: Vec<String>,
+    #[serde(default)]
+    pub system_libs: Vec<String>,
+    #[serde(default)]
+    pub defines: Vec<String>,
+    #[serde(default)]
+    pub cflags: Vec<String>,
+    #[serde(default)]
+    pub cxxflags: Vec<String>,
+    #[serde(default)]
+    pub frameworks: Vec<String>,
+    #[serde(default)]
+    pub frameworkdirs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConanV2GraphNode {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub cpp_info: HashMap<String, ConanV2CppInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConanV2Graph {
+    pub nodes: HashMap<String, ConanV2GraphNode>,
+}
+
+/// The dependency graph produced by `conan install --format=json` under
+/// Conan 2.x, as opposed to the flat `conanbuildinfo.json` that
+/// [`ConanBuildInfo`] parses for Conan 1.x.
+#[derive(Debug, Deserialize)]
+pub struct ConanV2BuildInfo {
+    pub graph: ConanV2Graph,
+}
+
+impl ConanV2BuildInfo {
+    pub fn create_from_json(json_content: &str) -> Option<ConanV2BuildInfo> {
+        serde_json::from_str(json_content).ok()
+    }
+
+    pub fn create_from_json_reader(reader: impl std::io::Read) -> Option<ConanV2BuildInfo> {
+        serde_json::from_reader(reader).ok()
+    }
+
+    /// Flattens each node's `cpp_info` into the same shape as
+    /// [`ConanBuildInfo::dependencies`], so callers (e.g.
+    /// [`crate::cargo::output_information_to_cargo`]) can stay agnostic to
+    /// which conan major version produced the build info.
+    ///
+    /// Merges every component under `cpp_info` (`root` plus any named ones,
+    /// e.g. `openssl::ssl`/`openssl::crypto`), since most Conan 2 packages
+    /// expose their libs/includes through components and leave `root`
+    /// mostly or entirely empty.
+    pub fn to_dependency_infos(&self) -> Vec<DependencyInfo> {
+        self.graph
+            .nodes
+            .values()
+            .filter_map(|node| {
+                let name = node.name.clone()?;
+                if node.cpp_info.is_empty() {
+                    return None;
+                }
+
+                let mut include_paths = Vec::new();
+                let mut lib_paths = Vec::new();
+                let mut bin_paths = Vec::new();
+                let mut libs = Vec::new();
+                let mut system_libs = Vec::new();
+                let mut defines = Vec::new();
+                let mut cflags = Vec::new();
+                let mut cxxflags = Vec::new();
+                let mut frameworks = Vec::new();
+                let mut framework_paths = Vec::new();
+
+                for cpp_info in node.cpp_info.values() {
+                    include_paths.extend(cpp_info.includedirs.iter().cloned());
+                    lib_paths.extend(cpp_info.libdirs.iter().cloned());
+                    bin_paths.extend(cpp_info.bindirs.iter().cloned());
+                    libs.extend(cpp_info.libs.iter().cloned());
+                    system_libs.extend(cpp_info.system_libs.iter().cloned());
+                    defines.extend(cpp_info.defines.iter().cloned());
+                    cflags.extend(cpp_info.cflags.iter().cloned());
+                    cxxflags.extend(cpp_info.cxxflags.iter().cloned());
+                    frameworks.extend(cpp_info.frameworks.iter().cloned());
+                    framework_paths.extend(cpp_info.frameworkdirs.iter().cloned());
+                }
+
+                Some(DependencyInfo {
+                    name,
+                    version: node.version.clone().unwrap_or_default(),
+                    description: None,
+                    rootpath: String::new(),
+                    sysroot: String::new(),
+                    include_paths,
+                    lib_paths,
+                    bin_paths,
+                    build_paths: Vec::new(),
+                    res_paths: Vec::new(),
+                    libs,
+                    system_libs,
+                    defines,
+                    cflags,
+                    cxxflags,
+                    sharedlinkflags: Vec::new(),
+                    exelinkflags: Vec::new(),
+                    frameworks,
+                    framework_paths,
+                    cppflags: Vec::new(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_v2_build_info_to_dependency_infos() {
+    let json_content = r#"
+    {
+        "graph": {
+            "nodes": {
+                "0": {
+                    "name": "app",
+                    "version": null,
+                    "cpp_info": {}
+                },
+                "1": {
+                    "name": "zlib",
+                    "version": "1.2.11",
+                    "cpp_info": {
+                        "root": {
+                            "includedirs": ["/path/to/zlib/include"],
+                            "libdirs": ["/path/to/zlib/lib"],
+                            "libs": ["z"]
+                        }
+                    }
+                },
+                "2": {
+                    "name": "openssl",
+                    "version": "3.0.0",
+                    "cpp_info": {
+                        "root": {},
+                        "ssl": {
+                            "includedirs": ["/path/to/openssl/include"],
+                            "libdirs": ["/path/to/openssl/lib"],
+                            "libs": ["ssl"]
+                        },
+                        "crypto": {
+                            "libdirs": ["/path/to/openssl/lib"],
+                            "libs": ["crypto"]
+                        }
+                    }
+                }
+            }
+        }
+    }
+    "#;
+    let build_info = ConanV2BuildInfo::create_from_json(json_content).unwrap();
+    let dependencies = build_info.to_dependency_infos();
+    assert_eq!(dependencies.len(), 2);
+    let zlib = dependencies.iter().find(|d| d.name == "zlib").unwrap();
+    assert_eq!(zlib.libs, vec!["z".to_string()]);
+
+    // Components (e.g. `openssl::ssl`/`openssl::crypto`) must be merged in,
+    // not just `root` (which here is empty).
+    let openssl = dependencies.iter().find(|d| d.name == "openssl").unwrap();
+    assert_eq!(openssl.libs.len(), 2);
+    assert!(openssl.libs.contains(&"ssl".to_string()));
+    assert!(openssl.libs.contains(&"crypto".to_string()));
+}
+
 #[test]
 fn test_install() {
     let conan = Conan::find_system_conan().unwrap();